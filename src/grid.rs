@@ -0,0 +1,225 @@
+// owning Grid<T>, row-major, so callers don't have to juggle plane[row][col]
+// bounds by hand
+
+use std::{collections::BTreeSet, fmt::Debug, ops::Index};
+
+use crate::util::Direction;
+
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.height && col < self.width {
+            Some(&self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        if row < 0 || col < 0 {
+            return None;
+        }
+        self.get(row as usize, col as usize)
+    }
+
+    pub fn in_bounds_2d(&self, row: isize, col: isize) -> bool {
+        self.get_signed(row, col).is_some()
+    }
+
+    // rows top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    // columns left to right, top to bottom within a column
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width)
+            .map(move |col| (0..self.height).map(move |row| &self.cells[row * self.width + col]))
+    }
+
+    pub fn iter_coords(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i / self.width, i % self.width), cell))
+    }
+
+    fn apply_index(&self, dir: Direction, row: usize, col: usize) -> Option<(usize, usize)> {
+        let (row_off, col_off) = dir.to_offset();
+        let off_row = row as isize + row_off;
+        let off_col = col as isize + col_off;
+
+        if self.in_bounds_2d(off_row, off_col) && !(row_off == 0 && col_off == 0) {
+            Some((off_row as usize, off_col as usize))
+        } else {
+            None
+        }
+    }
+
+    fn surrounding(
+        &self,
+        row: usize,
+        col: usize,
+        dirs: &'static [Direction],
+    ) -> impl Iterator<Item = (&T, usize, usize, Direction)> {
+        dirs.iter().filter_map(move |&dir| {
+            let (off_row, off_col) = self.apply_index(dir, row, col)?;
+            Some((self.get(off_row, off_col).unwrap(), off_row, off_col, dir))
+        })
+    }
+
+    pub fn surrounding_all(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (&T, usize, usize, Direction)> {
+        self.surrounding(row, col, &Direction::ALL)
+    }
+
+    pub fn surrounding_cardinal(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (&T, usize, usize, Direction)> {
+        self.surrounding(row, col, &Direction::CARDINAL)
+    }
+}
+
+impl<T: Debug + PartialEq> Grid<T> {
+    pub fn flood_fill(&self, row: usize, col: usize) -> BTreeSet<(usize, usize)> {
+        let mut seen = BTreeSet::new();
+        let mut region = BTreeSet::from([(row, col)]);
+        let t = &self[(row, col)];
+
+        let mut queue = vec![(row, col)];
+        while let Some((row, col)) = queue.pop() {
+            if seen.contains(&(row, col)) {
+                continue;
+            }
+            seen.insert((row, col));
+            region.insert((row, col));
+
+            for (elt, search_row, search_col, _) in self.surrounding_cardinal(row, col) {
+                if elt == t {
+                    queue.push((search_row, search_col));
+                }
+            }
+        }
+
+        region
+    }
+}
+
+impl Grid<char> {
+    pub fn parse_chars(s: &str) -> Grid<char> {
+        Grid::parse_with(s, |c| c)
+    }
+}
+
+impl<T> Grid<T> {
+    /// panics on a ragged grid, same as indexing a hand-rolled Vec<Vec<T>> would eventually
+    pub fn parse_with(s: &str, cell: impl Fn(char) -> T) -> Grid<T> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        let mut height = 0;
+
+        for line in s.lines().filter(|line| !line.is_empty()) {
+            let row_width = line.chars().count();
+            match width {
+                None => width = Some(row_width),
+                Some(width) => assert_eq!(
+                    width, row_width,
+                    "ragged grid: row {height} has width {row_width}, expected {width}"
+                ),
+            }
+
+            cells.extend(line.chars().map(&cell));
+            height += 1;
+        }
+
+        Grid {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col)
+            .unwrap_or_else(|| panic!("grid index out of bounds: ({row}, {col})"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_respects_bounds() {
+        let grid = Grid::parse_chars("ab\ncd");
+        assert_eq!(grid.get(0, 0), Some(&'a'));
+        assert_eq!(grid.get(1, 1), Some(&'d'));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+    }
+
+    #[test]
+    fn get_signed_rejects_negative() {
+        let grid = Grid::parse_chars("ab\ncd");
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(0, -1), None);
+        assert_eq!(grid.get_signed(1, 1), Some(&'d'));
+    }
+
+    #[test]
+    fn rows_and_cols_match_row_major_layout() {
+        let grid = Grid::parse_chars("ab\ncd");
+        let rows: Vec<&[char]> = grid.rows().collect();
+        assert_eq!(rows, vec![&['a', 'b'][..], &['c', 'd'][..]]);
+
+        let cols: Vec<Vec<char>> = grid.cols().map(|col| col.copied().collect()).collect();
+        assert_eq!(cols, vec![vec!['a', 'c'], vec!['b', 'd']]);
+    }
+
+    #[test]
+    fn iter_coords_pairs_cells_with_their_row_col() {
+        let grid = Grid::parse_chars("ab\ncd");
+        let coords: Vec<((usize, usize), char)> =
+            grid.iter_coords().map(|(pos, &c)| (pos, c)).collect();
+        assert_eq!(
+            coords,
+            vec![((0, 0), 'a'), ((0, 1), 'b'), ((1, 0), 'c'), ((1, 1), 'd'),]
+        );
+    }
+
+    #[test]
+    fn flood_fill_stays_within_equal_cells() {
+        let grid = Grid::parse_chars("aab\nabb");
+        let region = grid.flood_fill(0, 0);
+        assert_eq!(region, BTreeSet::from([(0, 0), (0, 1), (1, 0)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ragged grid")]
+    fn parse_with_panics_on_ragged_rows() {
+        Grid::parse_with("aa\na", |c| c);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod grid;
+pub mod parse;
+pub mod pathfind;
+pub mod util;
@@ -0,0 +1,131 @@
+// nom combinators for AoC input, so callers don't have to hand-roll
+// split/lines/parse to get to the Vec<Vec<T>> shape util.rs and grid.rs
+// already eat
+
+use nom::{
+    bytes::complete::{is_not, take_while1},
+    character::complete::{char, digit1, line_ending},
+    combinator::{map_res, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::separated_list1,
+    sequence::pair,
+    Err, IResult,
+};
+
+pub fn unsigned(input: &str) -> IResult<&str, u64> {
+    map_res(digit1, str::parse)(input)
+}
+
+pub fn signed(input: &str) -> IResult<&str, i64> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+// item, item, ... separated by runs of spaces/tabs, AoC's usual "list of
+// numbers" format (`1 2  3   4`)
+pub fn ws_separated<'a, O>(
+    item: impl FnMut(&'a str) -> IResult<&'a str, O> + Copy,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    move |input| separated_list1(take_while1(|c: char| c == ' ' || c == '\t'), item)(input)
+}
+
+// a block's non-blank lines. stops without eating a trailing line ending,
+// so the blank line separating blocks is left for blocks' own separator to
+// match against
+fn block_body(input: &str) -> IResult<&str, &str> {
+    recognize(separated_list1(
+        line_ending,
+        take_while1(|c: char| c != '\n'),
+    ))(input)
+}
+
+// split puzzle input into its blank-line-separated sections
+pub fn blocks(input: &str) -> IResult<&str, Vec<&str>> {
+    separated_list1(pair(line_ending, line_ending), block_body)(input)
+}
+
+// parses a grid of chars into Vec<Vec<T>> via cell. a char cell rejects is
+// a parse failure, not a silently dropped cell, and ragged rows are also a
+// failure instead of silently ragged output
+pub fn grid<T>(
+    cell: impl Fn(char) -> Option<T> + Copy,
+) -> impl FnMut(&str) -> IResult<&str, Vec<Vec<T>>> {
+    move |input| {
+        let (rest, lines) = separated_list1(line_ending, is_not("\n"))(input)?;
+
+        let mut rows = Vec::with_capacity(lines.len());
+        let mut width = None;
+
+        for line in lines {
+            let mut row = Vec::with_capacity(line.len());
+            for c in line.chars() {
+                match cell(c) {
+                    Some(value) => row.push(value),
+                    None => return Err(Err::Failure(Error::new(line, ErrorKind::Char))),
+                }
+            }
+
+            match width {
+                None => width = Some(row.len()),
+                Some(width) if width != row.len() => {
+                    return Err(Err::Failure(Error::new(line, ErrorKind::LengthValue)));
+                }
+                _ => {}
+            }
+
+            rows.push(row);
+        }
+
+        Ok((rest, rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_splits_every_section() {
+        assert_eq!(
+            blocks("a\nb\n\nc\nd\n\ne\nf"),
+            Ok(("", vec!["a\nb", "c\nd", "e\nf"]))
+        );
+    }
+
+    #[test]
+    fn blocks_single_section() {
+        assert_eq!(blocks("a\nb\nc"), Ok(("", vec!["a\nb\nc"])));
+    }
+
+    #[test]
+    fn grid_parses_rectangular_input() {
+        let cell = |c: char| {
+            if c == '#' || c == '.' {
+                Some(c == '#')
+            } else {
+                None
+            }
+        };
+        let (rest, rows) = grid(cell)("#..\n..#\n###").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            rows,
+            vec![
+                vec![true, false, false],
+                vec![false, false, true],
+                vec![true, true, true],
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_rejects_unmapped_characters() {
+        let cell = |c: char| if c == '#' { Some(()) } else { None };
+        assert!(grid(cell)("#.#").is_err());
+    }
+
+    #[test]
+    fn grid_rejects_ragged_rows() {
+        let cell = |c: char| Some(c);
+        assert!(grid(cell)("##\n#").is_err());
+    }
+}
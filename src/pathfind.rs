@@ -0,0 +1,246 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+};
+
+use crate::util::{surrounding_cardinal, Direction};
+
+// nodes are usually just (row, col), but stuff like the lava crucible day
+// needs extra state (direction, run length) along for the ride
+pub trait Node: Clone + Eq + Hash + Ord {
+    fn pos(&self) -> (usize, usize);
+
+    /// None rejects the move outright (too many steps in a row, etc), not
+    /// just infinite cost
+    fn step(&self, dir: Direction, new_pos: (usize, usize)) -> Option<Self>;
+}
+
+impl Node for (usize, usize) {
+    fn pos(&self) -> (usize, usize) {
+        *self
+    }
+
+    fn step(&self, _dir: Direction, new_pos: (usize, usize)) -> Option<Self> {
+        Some(new_pos)
+    }
+}
+
+/// default astar heuristic for plain (usize, usize) nodes
+pub fn manhattan(a: (usize, usize), b: (usize, usize)) -> u64 {
+    a.0.abs_diff(b.0) as u64 + a.1.abs_diff(b.1) as u64
+}
+
+fn reconstruct_path<N: Node>(came_from: &HashMap<N, N>, mut current: N) -> Vec<(usize, usize)> {
+    let mut path = vec![current.pos()];
+    while let Some(prev) = came_from.get(&current) {
+        current = prev.clone();
+        path.push(current.pos());
+    }
+    path.reverse();
+    path
+}
+
+fn search<T, N, FGoal, FCost, FHeuristic>(
+    plane: &[Vec<T>],
+    start: N,
+    mut is_goal: FGoal,
+    mut cost: FCost,
+    mut heuristic: FHeuristic,
+) -> Option<(u64, Vec<(usize, usize)>)>
+where
+    T: Debug,
+    N: Node,
+    FGoal: FnMut(&N) -> bool,
+    FCost: FnMut(&T, &N, (usize, usize), Direction) -> Option<u64>,
+    FHeuristic: FnMut(&N) -> u64,
+{
+    let mut best = HashMap::new();
+    best.insert(start.clone(), 0u64);
+
+    let mut came_from: HashMap<N, N> = HashMap::new();
+
+    let mut frontier = BinaryHeap::new();
+    frontier.push((Reverse(heuristic(&start)), 0u64, start.clone()));
+
+    while let Some((_, g, node)) = frontier.pop() {
+        if g > best[&node] {
+            // stale entry, a cheaper path to node showed up after this was pushed
+            continue;
+        }
+
+        if is_goal(&node) {
+            tracing::trace!("reached goal {:?} at cost {}", node.pos(), g);
+            return Some((g, reconstruct_path(&came_from, node)));
+        }
+
+        let (row, col) = node.pos();
+        for (value, new_row, new_col, dir) in surrounding_cardinal(plane, row, col) {
+            let Some(neighbor) = node.step(dir, (new_row, new_col)) else {
+                continue;
+            };
+
+            let Some(step_cost) = cost(value, &node, (new_row, new_col), dir) else {
+                continue;
+            };
+
+            let tentative = g + step_cost;
+
+            if best.get(&neighbor).is_some_and(|&known| known <= tentative) {
+                continue;
+            }
+
+            best.insert(neighbor.clone(), tentative);
+            came_from.insert(neighbor.clone(), node.clone());
+            frontier.push((
+                Reverse(tentative + heuristic(&neighbor)),
+                tentative,
+                neighbor,
+            ));
+        }
+    }
+
+    None
+}
+
+pub fn dijkstra<T, N, FGoal, FCost>(
+    plane: &[Vec<T>],
+    start: N,
+    is_goal: FGoal,
+    cost: FCost,
+) -> Option<(u64, Vec<(usize, usize)>)>
+where
+    T: Debug,
+    N: Node,
+    FGoal: FnMut(&N) -> bool,
+    FCost: FnMut(&T, &N, (usize, usize), Direction) -> Option<u64>,
+{
+    search(plane, start, is_goal, cost, |_| 0)
+}
+
+/// like dijkstra but with a heuristic added to the priority key (never to
+/// the stored cost)
+pub fn astar<T, N, FGoal, FCost, FHeuristic>(
+    plane: &[Vec<T>],
+    start: N,
+    is_goal: FGoal,
+    cost: FCost,
+    heuristic: FHeuristic,
+) -> Option<(u64, Vec<(usize, usize)>)>
+where
+    T: Debug,
+    N: Node,
+    FGoal: FnMut(&N) -> bool,
+    FCost: FnMut(&T, &N, (usize, usize), Direction) -> Option<u64>,
+    FHeuristic: FnMut(&N) -> u64,
+{
+    search(plane, start, is_goal, cost, heuristic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(s: &str) -> Vec<Vec<u64>> {
+        s.lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| c.to_digit(10).unwrap() as u64)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_path_and_reconstructs_it() {
+        let plane = grid("1111\n9991\n1111");
+        let (cost, path) = dijkstra(
+            &plane,
+            (0, 0),
+            |&pos| pos == (2, 3),
+            |&cost, _, _, _| Some(cost),
+        )
+        .unwrap();
+
+        // only col 3 is cheap to cross row 1, so it should detour around the 9s
+        assert_eq!(cost, 5);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(2, 3)));
+    }
+
+    #[test]
+    fn astar_respects_goal_predicate() {
+        let plane = grid("111\n111\n111");
+        let (cost, path) = astar(
+            &plane,
+            (0, 0),
+            |&pos| pos.0 == 2,
+            |&cost, _, _, _| Some(cost),
+            |&pos| manhattan(pos, (2, 0)),
+        )
+        .unwrap();
+
+        assert_eq!(cost, 2);
+        assert_eq!(path.last(), Some(&(2, 0)));
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    struct NoBacktrack {
+        pos: (usize, usize),
+        came_from: Option<Direction>,
+    }
+
+    impl Node for NoBacktrack {
+        fn pos(&self) -> (usize, usize) {
+            self.pos
+        }
+
+        fn step(&self, dir: Direction, new_pos: (usize, usize)) -> Option<Self> {
+            let opposite = self.came_from?;
+            if dir == opposite {
+                return None;
+            }
+            Some(NoBacktrack {
+                pos: new_pos,
+                came_from: Some(match dir {
+                    Direction::N => Direction::S,
+                    Direction::S => Direction::N,
+                    Direction::E => Direction::W,
+                    Direction::W => Direction::E,
+                    other => other,
+                }),
+            })
+        }
+    }
+
+    // with the direct (0,1)->(0,2) edge blocked, plain nodes still reach
+    // (0,2) through row 1. NoBacktrack's step returns None for any node
+    // whose came_from is None, so the start node has no moves at all and
+    // (0,2) is unreachable
+    fn block_b_to_c(
+        _cell: &u64,
+        _node: &impl Node,
+        new_pos: (usize, usize),
+        dir: Direction,
+    ) -> Option<u64> {
+        if new_pos == (0, 2) && dir == Direction::E {
+            None
+        } else {
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn step_returning_none_changes_reachability() {
+        let plane = grid("111\n111");
+
+        assert!(dijkstra(&plane, (0, 0), |&pos| pos == (0, 2), block_b_to_c).is_some());
+
+        let start = NoBacktrack {
+            pos: (0, 0),
+            came_from: None,
+        };
+        assert!(dijkstra(&plane, start, |node| node.pos == (0, 2), block_b_to_c).is_none());
+    }
+}
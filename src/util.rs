@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     collections::{BTreeSet, HashMap},
     fmt::Debug,
     hash::Hash,
@@ -84,6 +85,57 @@ impl Direction {
         Some(&plane[new_row][new_col])
     }
 
+    // like apply_index but wraps across the edges instead of returning None
+    // (toroidal grids). panics on an empty plane
+    pub fn apply_index_wrapping<T>(
+        &self,
+        plane: &[Vec<T>],
+        row: usize,
+        col: usize,
+    ) -> (usize, usize) {
+        assert!(
+            !plane.is_empty() && !plane[0].is_empty(),
+            "apply_index_wrapping: empty plane"
+        );
+
+        let height = plane.len() as isize;
+        let width = plane[0].len() as isize;
+        let (row_off, col_off) = self.to_offset();
+
+        let wrapped_row = (row as isize + row_off).rem_euclid(height);
+        let wrapped_col = (col as isize + col_off).rem_euclid(width);
+
+        (wrapped_row as usize, wrapped_col as usize)
+    }
+
+    // infinite-grid variant: row/col is the unbounded world position, keeps
+    // going past plane's edges instead of wrapping. returns the new world
+    // position alongside the wrapped index into the finite tile. panics on
+    // an empty plane
+    pub fn apply_index_wrapping_world<T>(
+        &self,
+        plane: &[Vec<T>],
+        row: isize,
+        col: isize,
+    ) -> ((isize, isize), (usize, usize)) {
+        assert!(
+            !plane.is_empty() && !plane[0].is_empty(),
+            "apply_index_wrapping_world: empty plane"
+        );
+
+        let height = plane.len() as isize;
+        let width = plane[0].len() as isize;
+        let (row_off, col_off) = self.to_offset();
+
+        let world = (row + row_off, col + col_off);
+        let wrapped = (
+            world.0.rem_euclid(height) as usize,
+            world.1.rem_euclid(width) as usize,
+        );
+
+        (world, wrapped)
+    }
+
     pub fn right_90(&self) -> Direction {
         use Direction::*;
         match self {
@@ -107,6 +159,40 @@ pub struct Surrounding<'a, T> {
     dir: usize,
     row: usize,
     col: usize,
+    pred: Option<Box<dyn Fn(SurroundingItem<'a, T>) -> bool + 'a>>,
+}
+
+impl<'a, T> Surrounding<'a, T> {
+    fn new(plane: &'a [Vec<T>], row: usize, col: usize, dirs: &'static [Direction]) -> Self {
+        Surrounding {
+            plane,
+            row,
+            col,
+            dir: 0,
+            dirs,
+            pred: None,
+        }
+    }
+
+    // like Surrounding::new, but neighbors also have to satisfy pred before
+    // being yielded, checked in the same loop that skips out-of-bounds
+    // directions rather than as a separate .filter() pass
+    pub fn with_predicate(
+        plane: &'a [Vec<T>],
+        row: usize,
+        col: usize,
+        dirs: &'static [Direction],
+        pred: impl Fn(SurroundingItem<'a, T>) -> bool + 'a,
+    ) -> Self {
+        Surrounding {
+            plane,
+            row,
+            col,
+            dir: 0,
+            dirs,
+            pred: Some(Box::new(pred)),
+        }
+    }
 }
 
 impl<'a, T: Debug> Iterator for Surrounding<'a, T> {
@@ -122,16 +208,25 @@ impl<'a, T: Debug> Iterator for Surrounding<'a, T> {
         self.dir += 1;
 
         if let Some((off_row, off_col)) = dir.apply_index(self.plane, self.row, self.col) {
+            let item = (&self.plane[off_row][off_col], off_row, off_col, dir);
+
+            if let Some(pred) = &self.pred {
+                if !pred(item) {
+                    tracing::trace!("{:?} {},{} filtered out", item.0, off_row, off_col);
+                    return self.next();
+                }
+            }
+
             tracing::trace!(
                 "{:?} {},{} is {:?} of {},{}",
-                self.plane[off_row][off_col],
+                item.0,
                 off_row,
                 off_col,
                 dir,
                 self.row,
                 self.col
             );
-            Some((&self.plane[off_row][off_col], off_row, off_col, dir))
+            Some(item)
         } else {
             tracing::trace!("nothing is {:?} of {},{}", dir, self.row, self.col);
             self.next()
@@ -152,13 +247,7 @@ fn surrounding<'a, T: Debug>(
         col,
         dirs,
     );
-    Surrounding {
-        plane,
-        row,
-        col,
-        dir: 0,
-        dirs,
-    }
+    Surrounding::new(plane, row, col, dirs)
 }
 
 pub fn surrounding_all<T: Debug>(
@@ -177,6 +266,36 @@ pub fn surrounding_cardinal<T: Debug>(
     surrounding(plane, row, col, &Direction::CARDINAL)
 }
 
+// like surrounding_cardinal but wraps across the edges instead of skipping
+// them, so every direction always yields a neighbor
+pub fn surrounding_wrapping<T: Debug>(
+    plane: &[Vec<T>],
+    row: usize,
+    col: usize,
+) -> impl Iterator<Item = SurroundingItem<'_, T>> + '_ {
+    Direction::CARDINAL.iter().map(move |&dir| {
+        let (wrapped_row, wrapped_col) = dir.apply_index_wrapping(plane, row, col);
+        (
+            &plane[wrapped_row][wrapped_col],
+            wrapped_row,
+            wrapped_col,
+            dir,
+        )
+    })
+}
+
+// like surrounding_all/surrounding_cardinal but only yields neighbors
+// matching pred, filtered inline instead of a separate .filter() pass
+pub fn surrounding_where<'a, T: Debug>(
+    plane: &'a [Vec<T>],
+    row: usize,
+    col: usize,
+    dirs: &'static [Direction],
+    pred: impl Fn(SurroundingItem<'a, T>) -> bool + 'a,
+) -> impl Iterator<Item = SurroundingItem<'a, T>> {
+    Surrounding::with_predicate(plane, row, col, dirs, pred)
+}
+
 pub fn flood_fill<T: Debug + PartialEq<T>>(
     plane: &[Vec<T>],
     row: usize,
@@ -203,3 +322,246 @@ pub fn flood_fill<T: Debug + PartialEq<T>>(
 
     region
 }
+
+// union-find over cells indexed by row * width + col, just enough for
+// label_regions to union same-valued neighbors in one pass instead of
+// running a separate flood_fill per region
+struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return;
+        }
+
+        match self.rank[root_a].cmp(&self.rank[root_b]) {
+            Ordering::Less => self.parent[root_a] = root_b,
+            Ordering::Greater => self.parent[root_b] = root_a,
+            Ordering::Equal => {
+                self.parent[root_b] = root_a;
+                self.rank[root_a] += 1;
+            }
+        }
+    }
+}
+
+// row/col grid of component ids, plus the cells belonging to each component
+pub type RegionLabels = (Vec<Vec<usize>>, Vec<BTreeSet<(usize, usize)>>);
+
+// labels every connected component of equal-valued cells at once, instead
+// of flood_filling from a single seed each time
+pub fn label_regions<T: Debug + PartialEq>(plane: &[Vec<T>]) -> RegionLabels {
+    let height = plane.len();
+    let width = if height == 0 { 0 } else { plane[0].len() };
+
+    let mut dsu = UnionFind::new(width * height);
+
+    for (row, cells) in plane.iter().enumerate() {
+        for (col, value) in cells.iter().enumerate() {
+            for (elt, neighbor_row, neighbor_col, dir) in surrounding_cardinal(plane, row, col) {
+                if matches!(dir, Direction::N | Direction::W) && elt == value {
+                    dsu.union(row * width + col, neighbor_row * width + neighbor_col);
+                }
+            }
+        }
+    }
+
+    let mut labels = vec![vec![0usize; width]; height];
+    let mut label_of_root = HashMap::new();
+    let mut cells: Vec<BTreeSet<(usize, usize)>> = Vec::new();
+
+    for (row, label_row) in labels.iter_mut().enumerate() {
+        for (col, label) in label_row.iter_mut().enumerate() {
+            let root = dsu.find(row * width + col);
+            let id = *label_of_root.entry(root).or_insert_with(|| {
+                cells.push(BTreeSet::new());
+                cells.len() - 1
+            });
+            *label = id;
+            cells[id].insert((row, col));
+        }
+    }
+
+    (labels, cells)
+}
+
+pub fn region_area(region: &BTreeSet<(usize, usize)>) -> usize {
+    region.len()
+}
+
+// cardinal edges leaving region, either off the grid or onto a cell from a
+// different region
+pub fn region_perimeter<T>(plane: &[Vec<T>], region: &BTreeSet<(usize, usize)>) -> usize {
+    let mut perimeter = 0;
+
+    for &(row, col) in region {
+        for dir in Direction::CARDINAL {
+            match dir.apply_index(plane, row, col) {
+                Some(neighbor) if region.contains(&neighbor) => {}
+                _ => perimeter += 1,
+            }
+        }
+    }
+
+    perimeter
+}
+
+// number of sides = number of corners: a cell is a convex corner if both
+// orthogonal neighbors on that corner are outside the region, and a
+// concave corner if both are inside but the diagonal between them isn't
+pub fn region_corners<T>(plane: &[Vec<T>], region: &BTreeSet<(usize, usize)>) -> usize {
+    let in_region = |step: Option<(usize, usize)>| step.is_some_and(|pos| region.contains(&pos));
+
+    let mut corners = 0;
+
+    for &(row, col) in region {
+        for (ortho_a, ortho_b, diag) in [
+            (Direction::N, Direction::W, Direction::NW),
+            (Direction::N, Direction::E, Direction::NE),
+            (Direction::S, Direction::W, Direction::SW),
+            (Direction::S, Direction::E, Direction::SE),
+        ] {
+            let a = in_region(ortho_a.apply_index(plane, row, col));
+            let b = in_region(ortho_b.apply_index(plane, row, col));
+            let d = in_region(diag.apply_index(plane, row, col));
+
+            if (!a && !b) || (a && b && !d) {
+                corners += 1;
+            }
+        }
+    }
+
+    corners
+}
+
+#[cfg(test)]
+mod surrounding_tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    // center is (1,1)=4; N=1, S=7, E=6, W=3 — only E is even
+    fn even_neighbor_plane() -> Vec<Vec<i32>> {
+        vec![vec![0, 1, 0], vec![3, 4, 6], vec![0, 7, 0]]
+    }
+
+    #[test]
+    fn surrounding_where_only_yields_matching_neighbors() {
+        let plane = even_neighbor_plane();
+        let found: Vec<i32> =
+            surrounding_where(&plane, 1, 1, &Direction::CARDINAL, |(&value, _, _, _)| {
+                value % 2 == 0
+            })
+            .map(|(&value, _, _, _)| value)
+            .collect();
+        assert_eq!(found, vec![6]);
+    }
+
+    #[test]
+    fn surrounding_where_filters_lazily_not_after_collecting() {
+        // if the predicate were applied after collecting every neighbor,
+        // finding the first match would still cost 4 calls. Checking the
+        // call count after just one .next() proves it's evaluated inline,
+        // direction by direction, instead.
+        let plane = even_neighbor_plane();
+        let calls = Cell::new(0);
+
+        let mut iter =
+            surrounding_where(&plane, 1, 1, &Direction::CARDINAL, |(&value, _, _, _)| {
+                calls.set(calls.get() + 1);
+                value % 2 == 0
+            });
+
+        let first = iter.next();
+        assert_eq!(first.map(|(&value, _, _, _)| value), Some(6));
+        // N and S are checked and rejected before E matches: 3 calls, not 4
+        assert_eq!(calls.get(), 3);
+    }
+}
+
+#[cfg(test)]
+mod wrapping_tests {
+    use super::*;
+
+    #[test]
+    fn apply_index_wrapping_wraps_off_the_top_edge() {
+        let plane = vec![vec![0; 3]; 3];
+        assert_eq!(Direction::N.apply_index_wrapping(&plane, 0, 1), (2, 1));
+        assert_eq!(Direction::S.apply_index_wrapping(&plane, 2, 1), (0, 1));
+    }
+
+    #[test]
+    fn apply_index_wrapping_world_keeps_world_pos_unbounded() {
+        let plane = vec![vec![0; 3]; 3];
+        let (world, wrapped) = Direction::N.apply_index_wrapping_world(&plane, 0, 1);
+        assert_eq!(world, (-1, 1));
+        assert_eq!(wrapped, (2, 1));
+
+        // stepping N again keeps going negative instead of wrapping back to 0
+        let (world, wrapped) = Direction::N.apply_index_wrapping_world(&plane, -1, 1);
+        assert_eq!(world, (-2, 1));
+        assert_eq!(wrapped, (1, 1));
+    }
+
+    #[test]
+    fn surrounding_wrapping_yields_all_four_cardinals() {
+        let plane = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        let found: BTreeSet<(usize, usize)> = surrounding_wrapping(&plane, 0, 0)
+            .map(|(_, row, col, _)| (row, col))
+            .collect();
+        // from the top-left corner, every cardinal neighbor wraps to the far edge
+        assert_eq!(found, BTreeSet::from([(2, 0), (1, 0), (0, 1), (0, 2)]));
+    }
+}
+
+#[cfg(test)]
+mod region_tests {
+    use super::*;
+
+    fn lines(s: &str) -> Vec<Vec<char>> {
+        s.lines().map(|line| line.chars().collect()).collect()
+    }
+
+    #[test]
+    fn label_regions_separates_components() {
+        let plane = lines("AAA\nABA\nAAA");
+        let (labels, cells) = label_regions(&plane);
+
+        assert_eq!(cells.len(), 2);
+        // every border cell shares the outer "A" region's label
+        assert_eq!(labels[0][0], labels[2][2]);
+        // the lone "B" in the middle is its own region
+        assert_ne!(labels[1][1], labels[0][0]);
+        assert_eq!(region_area(&cells[labels[1][1]]), 1);
+    }
+
+    #[test]
+    fn region_perimeter_and_corners_of_a_square() {
+        let plane = lines("AA\nAA");
+        let (_, cells) = label_regions(&plane);
+
+        assert_eq!(cells.len(), 1);
+        assert_eq!(region_area(&cells[0]), 4);
+        assert_eq!(region_perimeter(&plane, &cells[0]), 8);
+        // a solid rectangle has exactly 4 sides
+        assert_eq!(region_corners(&plane, &cells[0]), 4);
+    }
+}